@@ -6,10 +6,19 @@
 //! See the docs on [`Fibonacci::f`] for a function to get a specific F*ₙ* value.
 //!
 //! See the docs on [`Fibonacci`] for an [`Iterator`].
+//!
+//! Enable the `alloc` feature to get [`FibCache`] and `BigUint` support
+//! without requiring full `std`.
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+extern crate alloc;
 
 use core::{fmt::Debug, iter::FusedIterator, mem};
 
-use num::{CheckedAdd, One, Zero};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::{vec, vec::Vec};
+
+use num::{CheckedAdd, CheckedMul, CheckedSub, One, Zero};
 
 /// An easy way to get numbers in the Fibonacci series.
 ///
@@ -97,6 +106,10 @@ use num::{CheckedAdd, One, Zero};
 pub struct Fibonacci<T> {
     previous: Option<T>,
     current: Option<T>,
+    // The first value `next()` should yield, overriding whatever the
+    // `previous`/`current` pair would otherwise produce. Only ever set (and
+    // then immediately consumed) by `from_terms`.
+    pending: Option<T>,
 }
 
 impl<T> Debug for Fibonacci<T>
@@ -107,6 +120,7 @@ where
         f.debug_struct("Fibonacci")
             .field("previous", &self.previous)
             .field("current", &self.current)
+            .field("pending", &self.pending)
             .finish()
     }
 }
@@ -121,6 +135,7 @@ where
         Self {
             previous: self.previous.clone(),
             current: self.current.clone(),
+            pending: self.pending.clone(),
         }
     }
 }
@@ -131,6 +146,7 @@ impl<T> Default for Fibonacci<T> {
         Self {
             previous: None,
             current: None,
+            pending: None,
         }
     }
 }
@@ -141,6 +157,51 @@ impl<T> Fibonacci<T> {
         // then the last iteration caused an overflow.
         self.current.is_none() && self.previous.is_some()
     }
+
+    /// Creates an iterator that yields Fibonacci numbers only while they're
+    /// less than or equal to `limit`, then stops, rather than stopping only
+    /// once `T` would overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fibs::Fibonacci;
+    ///
+    /// let nums: Vec<_> = Fibonacci::up_to(10u32).collect();
+    /// assert_eq!(vec![0, 1, 1, 2, 3, 5, 8], nums);
+    /// ```
+    pub fn up_to(limit: T) -> UpTo<T> {
+        UpTo {
+            inner: Self::default(),
+            limit,
+        }
+    }
+
+    /// Creates an iterator seeded with arbitrary starting terms, rather
+    /// than the standard F*₀* = 0, F*₁* = 1.
+    ///
+    /// This turns `Fibonacci` into a generator for any additive sequence of
+    /// the form *f(n) = f(n - 1) + f(n - 2)*, such as the Lucas numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fibs::Fibonacci;
+    ///
+    /// // The Lucas numbers: 2, 1, 3, 4, 7, 11, ...
+    /// let lucas: Vec<_> = Fibonacci::from_terms(2u32, 1).take(6).collect();
+    /// assert_eq!(vec![2, 1, 3, 4, 7, 11], lucas);
+    /// ```
+    pub fn from_terms(a: T, b: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            previous: Some(a.clone()),
+            current: Some(b),
+            pending: Some(a),
+        }
+    }
 }
 
 impl<T> Fibonacci<T>
@@ -188,7 +249,7 @@ where
     /// assert_eq!(Ok(332825110087067562321196029789634457848), n);
     ///
     #[cfg_attr(
-        feature = "std",
+        any(feature = "std", feature = "alloc"),
         doc = r##"
 // Or, if you want _really_ big numbers, you can use other types.
 let n = Fibonacci::<num::BigUint>::f(1000);
@@ -266,6 +327,75 @@ assert_eq!(
     }
 }
 
+impl<T> Fibonacci<T>
+where
+    T: Clone + CheckedAdd + CheckedSub + CheckedMul + Zero + One,
+{
+    /// Returns the F*ₙ* value in *O*(log *n*) time, using the fast-doubling
+    /// identities, rather than the *O*(*n*) iteration used by
+    /// [`Fibonacci::f`].
+    ///
+    /// Returns `None` if F*ₙ* would overflow `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fibs::Fibonacci;
+    ///
+    /// assert_eq!(Some(34), Fibonacci::f_fast(9));
+    /// assert_eq!(Fibonacci::<i32>::f(9).ok(), Fibonacci::f_fast(9));
+    /// ```
+    ///
+    /// If you try to get a number too big for your target numeric type,
+    /// you'll get `None` back, rather than the maximum value for the type:
+    /// ```
+    /// # use fibs::Fibonacci;
+    /// #
+    /// assert_eq!(None, Fibonacci::<u8>::f_fast(14));
+    /// assert_eq!(Some(233), Fibonacci::<u8>::f_fast(13));
+    /// ```
+    pub fn f_fast(n: usize) -> Option<T> {
+        // We only need `F(n / 2)` and `F(n / 2 + 1)` to compute `F(n)` itself;
+        // going through `fib_pair(n)` directly would also require `F(n + 1)`,
+        // which can overflow `T` even when `F(n)` doesn't.
+        let (a, b) = fib_pair::<T>(n / 2)?;
+
+        if n.is_multiple_of(2) {
+            // F(2k) = F(k) * (2 * F(k + 1) - F(k))
+            let two_b_minus_a = b.checked_add(&b)?.checked_sub(&a)?;
+            a.checked_mul(&two_b_minus_a)
+        } else {
+            // F(2k + 1) = F(k + 1)^2 + F(k)^2
+            a.checked_mul(&a)?.checked_add(&b.checked_mul(&b)?)
+        }
+    }
+}
+
+/// Returns `(F(n), F(n + 1))` using the fast-doubling identities, or `None`
+/// if either would overflow `T`.
+fn fib_pair<T>(n: usize) -> Option<(T, T)>
+where
+    T: Clone + CheckedAdd + CheckedSub + CheckedMul + Zero + One,
+{
+    if n == 0 {
+        return Some((T::zero(), T::one()));
+    }
+
+    let (a, b) = fib_pair::<T>(n / 2)?;
+
+    // F(2k)     = F(k) * (2 * F(k + 1) - F(k))
+    // F(2k + 1) = F(k + 1)^2 + F(k)^2
+    let two_b_minus_a = b.checked_add(&b)?.checked_sub(&a)?;
+    let f_2k = a.checked_mul(&two_b_minus_a)?;
+    let f_2k_plus_1 = a.checked_mul(&a)?.checked_add(&b.checked_mul(&b)?)?;
+
+    if n.is_multiple_of(2) {
+        Some((f_2k, f_2k_plus_1))
+    } else {
+        Some((f_2k_plus_1.clone(), f_2k.checked_add(&f_2k_plus_1)?))
+    }
+}
+
 impl<T> Iterator for Fibonacci<T>
 where
     T: Clone + CheckedAdd + Zero + One,
@@ -273,6 +403,13 @@ where
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
+        // `from_terms` pre-loads `previous`/`current` so the *second* call
+        // computes the right thing; this overrides just the first call's
+        // output to be the seed that `previous` was pre-loaded with.
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+
         if self.has_overflowed() {
             return None;
         }
@@ -289,11 +426,143 @@ where
 
 impl<T> FusedIterator for Fibonacci<T> where T: Clone + CheckedAdd + Zero + One {}
 
+/// An iterator that yields Fibonacci numbers only while they're less than
+/// or equal to a limit, then fuses, rather than stopping only once `T`
+/// would overflow.
+///
+/// Returned by [`Fibonacci::up_to`].
+pub struct UpTo<T> {
+    inner: Fibonacci<T>,
+    limit: T,
+}
+
+impl<T> Debug for UpTo<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UpTo")
+            .field("inner", &self.inner)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<T> Clone for UpTo<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            limit: self.limit.clone(),
+        }
+    }
+}
+
+impl<T> Iterator for UpTo<T>
+where
+    T: Clone + CheckedAdd + Zero + One + PartialOrd,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next()?;
+
+        if value > self.limit {
+            // Fuse, the same way `Fibonacci` fuses on overflow.
+            self.inner.current = None;
+            return None;
+        }
+
+        Some(value)
+    }
+}
+
+impl<T> FusedIterator for UpTo<T> where T: Clone + CheckedAdd + Zero + One + PartialOrd {}
+
+/// A growable cache of Fibonacci numbers for cheap, repeated, random-access
+/// lookups.
+///
+/// Unlike [`Fibonacci::f`], which walks the sequence from *F₀* on every
+/// call, [`FibCache::get`] only computes the terms it hasn't already seen,
+/// and remembers them for next time.
+///
+/// # Examples
+///
+/// ```
+/// use fibs::FibCache;
+///
+/// let mut cache = FibCache::<u8>::new();
+/// assert_eq!(Ok(34), cache.get(9));
+/// assert_eq!(Ok(34), cache.get(9), "served from the cache this time");
+/// assert_eq!(Err((13, 233)), cache.get(14));
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Debug, Clone)]
+pub struct FibCache<T> {
+    terms: Vec<T>,
+    overflowed: bool,
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<T> FibCache<T>
+where
+    T: Clone + CheckedAdd + Zero + One,
+{
+    /// Creates a new cache, holding only *F₀* and *F₁*.
+    pub fn new() -> Self {
+        Self {
+            terms: vec![T::zero(), T::one()],
+            overflowed: false,
+        }
+    }
+
+    /// Returns the F*ₙ* value, computing and caching any terms up to it
+    /// that haven't been seen yet.
+    ///
+    /// If F*ₙ* would overflow `T`, `Err` is returned with a tuple
+    /// indicating the largest *ₙ* that would *not* overflow `T`, as well
+    /// as what that F*ₙ* value is, same as [`Fibonacci::f`].
+    pub fn get(&mut self, n: usize) -> Result<T, (usize, T)> {
+        let max_n = self.terms.len() - 1;
+        if self.overflowed && n >= self.terms.len() {
+            return Err((max_n, self.terms[max_n].clone()));
+        }
+
+        while self.terms.len() <= n {
+            let len = self.terms.len();
+            match self.terms[len - 2].checked_add(&self.terms[len - 1]) {
+                Some(next) => self.terms.push(next),
+                None => {
+                    self.overflowed = true;
+                    let max_n = len - 1;
+                    return Err((max_n, self.terms[max_n].clone()));
+                }
+            }
+        }
+
+        Ok(self.terms[n].clone())
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<T> Default for FibCache<T>
+where
+    T: Clone + CheckedAdd + Zero + One,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use core::fmt::Debug;
 
-    use num::{CheckedAdd, One, Zero};
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    use alloc::{vec, vec::Vec};
+    use num::{CheckedAdd, CheckedMul, CheckedSub, One, Zero};
 
     use super::Fibonacci;
 
@@ -317,6 +586,61 @@ mod test {
         assert_eq!(Some(2), f.next());
     }
 
+    #[test]
+    fn up_to() {
+        assert_eq!(
+            vec![0, 1, 1, 2, 3, 5, 8],
+            Fibonacci::up_to(10u32).collect::<Vec<_>>(),
+        );
+
+        assert_eq!(
+            vec![0, 1, 1, 2, 3, 5, 8, 13],
+            Fibonacci::up_to(13u32).collect::<Vec<_>>(),
+            "The limit itself should be included"
+        );
+
+        // Fused: still `None` after the limit is reached.
+        let mut iter = Fibonacci::up_to(1u8);
+        assert_eq!(Some(0), iter.next());
+        assert_eq!(Some(1), iter.next());
+        assert_eq!(Some(1), iter.next());
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn from_terms() {
+        // The Lucas numbers.
+        assert_eq!(
+            vec![2, 1, 3, 4, 7, 11, 18, 29],
+            Fibonacci::from_terms(2u32, 1).take(8).collect::<Vec<_>>(),
+        );
+
+        // Seeding with the standard terms should behave exactly like `Default`.
+        assert_eq!(
+            Fibonacci::default().take(10).collect::<Vec<u32>>(),
+            Fibonacci::from_terms(0u32, 1).take(10).collect::<Vec<_>>(),
+        );
+
+        // Overflow detection still applies to a seeded iterator.
+        let mut lucas = Fibonacci::from_terms(2u8, 1);
+        assert_eq!(Some(2), lucas.next());
+        assert_eq!(Some(1), lucas.next());
+        assert_eq!(Some(3), lucas.next());
+        assert_eq!(Some(4), lucas.next());
+        assert_eq!(Some(7), lucas.next());
+        assert_eq!(Some(11), lucas.next());
+        assert_eq!(Some(18), lucas.next());
+        assert_eq!(Some(29), lucas.next());
+        assert_eq!(Some(47), lucas.next());
+        assert_eq!(Some(76), lucas.next());
+        assert_eq!(Some(123), lucas.next());
+        assert_eq!(Some(199), lucas.next());
+        // 123 + 199 = 322, which overflows `u8`.
+        assert_eq!(None, lucas.next());
+        assert_eq!(None, lucas.next(), "Should still be fused after overflow");
+    }
+
     #[track_caller]
     fn known_max<T>(max_n: usize, expect: T)
     where
@@ -349,7 +673,49 @@ mod test {
     known_max!(i128, 184, 127127879743834334146972278486287885163);
     known_max!(u128, 186, 332825110087067562321196029789634457848);
 
-    #[cfg(feature = "std")]
+    #[test]
+    fn f_fast_matches_f() {
+        for n in 0..200 {
+            assert_eq!(
+                Fibonacci::<u128>::f(n).ok(),
+                Fibonacci::f_fast(n),
+                "f_fast({n}) should match f({n})"
+            );
+        }
+    }
+
+    #[track_caller]
+    fn known_max_fast<T>(max_n: usize, expect: T)
+    where
+        T: Debug + Default + Clone + PartialEq + CheckedAdd + CheckedSub + CheckedMul + Zero + One,
+    {
+        assert_eq!(Some(expect), Fibonacci::f_fast(max_n));
+        assert_eq!(None, Fibonacci::<T>::f_fast(max_n + 1));
+    }
+
+    macro_rules! known_max_fast {
+        ($t:ty, $max_n:expr, $expect:expr) => {
+            paste::item! {
+                #[test]
+                fn [<max_n_fast_ $t>]() {
+                    known_max_fast::<$t>($max_n, $expect)
+                }
+            }
+        };
+    }
+
+    known_max_fast!(i8, 11, 89);
+    known_max_fast!(u8, 13, 233);
+    known_max_fast!(i16, 23, 28657);
+    known_max_fast!(u16, 24, 46368);
+    known_max_fast!(i32, 46, 1836311903);
+    known_max_fast!(u32, 47, 2971215073);
+    known_max_fast!(i64, 92, 7540113804746346429);
+    known_max_fast!(u64, 93, 12200160415121876738);
+    known_max_fast!(i128, 184, 127127879743834334146972278486287885163);
+    known_max_fast!(u128, 186, 332825110087067562321196029789634457848);
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
     #[ignore = "Only run this if you've got time on your hands. \
         It takes at least the better part of an hour."]
     #[test]
@@ -363,4 +729,36 @@ mod test {
             "Should be able to count to `usize::MAX`"
         );
     }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[test]
+    fn fib_cache() {
+        use super::FibCache;
+
+        let mut cache = FibCache::<u8>::new();
+
+        assert_eq!(Ok(0), cache.get(0));
+        assert_eq!(Ok(34), cache.get(9));
+        assert_eq!(
+            Ok(34),
+            cache.get(9),
+            "Must return the same value when served from the cache"
+        );
+        assert_eq!(
+            Ok(233),
+            cache.get(13),
+            "Must be able to get the largest value that will fit in the target type"
+        );
+        assert_eq!(Err((13, 233)), cache.get(14));
+        assert_eq!(
+            Err((13, 233)),
+            cache.get(usize::MAX),
+            "Must remember that it's already overflowed"
+        );
+        assert_eq!(
+            Ok(233),
+            cache.get(13),
+            "Must still be able to get cached values after an overflow"
+        );
+    }
 }